@@ -1,7 +1,10 @@
 use std::fs::File;
-use bio::io::fasta;
+use std::io::{BufRead, BufReader, Write};
+use bio::io::{fasta, fastq};
 use plotters::prelude::*;
 
+mod stats;
+
 
 #[derive(Debug, Default)]
 struct BaseCount {
@@ -12,10 +15,10 @@ struct BaseCount {
     other: u64,
 }
 
-fn count_bases(record: &fasta::Record) -> BaseCount {
+fn count_bases(seq: &[u8]) -> BaseCount {
     let mut count = BaseCount::default();
 
-    for base in record.seq() {
+    for base in seq {
         match base {
             b'A' => count.a += 1,
             b'C' => count.c += 1,
@@ -28,15 +31,24 @@ fn count_bases(record: &fasta::Record) -> BaseCount {
     count
 }
 
-fn get_total_gc_content(record: &fasta::Record) -> f64 {
-    let count = count_bases(record);
-    
+fn get_total_gc_content(seq: &[u8]) -> f64 {
+    let count = count_bases(seq);
+
     let at = (count.a + count.t) as f64;
     let gc = (count.g + count.c) as f64;
 
     gc / (at + gc)
 }
 
+// Replaces bases whose Phred quality score (qual byte - 33) falls below
+// `min_qual` with 'N', so they fall into BaseCount::other downstream instead
+// of inflating GC estimates.
+fn mask_low_quality(seq: &[u8], qual: &[u8], min_qual: u8) -> Vec<u8> {
+    seq.iter().zip(qual.iter())
+        .map(|(&base, &q)| if q.saturating_sub(33) < min_qual { b'N' } else { base })
+        .collect()
+}
+
 
 struct SlidingWindowAverage<'a> {
     data: &'a [u8],
@@ -44,6 +56,11 @@ struct SlidingWindowAverage<'a> {
     idx: usize,
     step: usize,
     size: usize,
+    // The first window's sum is computed in `new()`, before `next()` has been
+    // called at all; this tracks whether that first window still needs to be
+    // yielded, so callers pairing window averages with `(0..).step_by(step)`
+    // get a start position of 0 for the first window instead of `step`.
+    first: bool,
 }
 
 impl SlidingWindowAverage<'_> {
@@ -57,12 +74,13 @@ impl SlidingWindowAverage<'_> {
             };
         }
 
-        SlidingWindowAverage { 
-            data, 
-            sum: first_window_sum, 
+        SlidingWindowAverage {
+            data,
+            sum: first_window_sum,
             idx: 0,
             step,
-            size
+            size,
+            first: true,
         }
     }
 
@@ -71,8 +89,13 @@ impl SlidingWindowAverage<'_> {
 impl Iterator for SlidingWindowAverage<'_> {
     type Item = f32;
 
-    // its 2AM please dont judge 
+    // its 2AM please dont judge
     fn next(&mut self) -> Option<Self::Item> {
+        if self.first {
+            self.first = false;
+            return Some(self.sum as f32 / self.size as f32);
+        }
+
         if self.idx + self.size + self.step < self.data.len() - 1 {
             // Subtract first n=step items
             for i in 0..self.step {
@@ -102,17 +125,181 @@ impl Iterator for SlidingWindowAverage<'_> {
 }
 
 
-fn plot(filename: &str, title: &str, record: &fasta::Record) {
-    const SIZE: usize = 100000;
-    const STEP: usize = 10000;
+// Like SlidingWindowAverage, but tracks G and C counts separately so it can
+// yield the GC skew (G - C) / (G + C) per window instead of a combined
+// GC average.
+struct SlidingWindowSkew<'a> {
+    data: &'a [u8],
+    g_count: u64,
+    c_count: u64,
+    idx: usize,
+    step: usize,
+    size: usize,
+    // See SlidingWindowAverage::first.
+    first: bool,
+}
+
+impl SlidingWindowSkew<'_> {
+    fn new(data: &[u8], size: usize, step: usize) -> SlidingWindowSkew {
+        let mut g_count = 0;
+        let mut c_count = 0;
+
+        for i in 0..size {
+            match data[i] {
+                b'G' => g_count += 1,
+                b'C' => c_count += 1,
+                _ => {}
+            }
+        }
+
+        SlidingWindowSkew {
+            data,
+            g_count,
+            c_count,
+            idx: 0,
+            step,
+            size,
+            first: true,
+        }
+    }
+}
+
+impl Iterator for SlidingWindowSkew<'_> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.first {
+            self.first = false;
+            let total = self.g_count + self.c_count;
+            return Some(if total == 0 {
+                0.0
+            } else {
+                (self.g_count as f32 - self.c_count as f32) / total as f32
+            });
+        }
+
+        if self.idx + self.size + self.step < self.data.len() - 1 {
+            // Subtract first n=step items
+            for i in 0..self.step {
+                match self.data[self.idx + i] {
+                    b'G' => self.g_count -= 1,
+                    b'C' => self.c_count -= 1,
+                    _ => {}
+                }
+            }
+
+            // Add next n=step items
+            for i in 0..self.step {
+                match self.data[self.idx + self.size + i] {
+                    b'G' => self.g_count += 1,
+                    b'C' => self.c_count += 1,
+                    _ => {}
+                }
+            }
+
+            self.idx += self.step;
+
+            let total = self.g_count + self.c_count;
+            Some(if total == 0 {
+                0.0
+            } else {
+                (self.g_count as f32 - self.c_count as f32) / total as f32
+            })
+        } else {
+            None
+        }
+    }
+}
+
+// Running prefix sum of per-window GC skew. Its global minimum and maximum
+// mark the likely replication origin and terminus in bacterial genomes.
+fn cumulative_skew(skew: &[f32]) -> Vec<f32> {
+    let mut running = 0.0;
+
+    skew.iter().map(|s| {
+        running += s;
+        running
+    }).collect()
+}
+
+// Returns the (min_index, max_index) of `values`, or `None` if empty.
+fn find_extrema(values: &[f32]) -> Option<(usize, usize)> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut min_idx = 0;
+    let mut max_idx = 0;
+
+    for (i, &v) in values.iter().enumerate() {
+        if v < values[min_idx] {
+            min_idx = i;
+        }
+        if v > values[max_idx] {
+            max_idx = i;
+        }
+    }
+
+    Some((min_idx, max_idx))
+}
+
+// Clamps `size` so the sliding window never indexes past the end of the
+// sequence, which would otherwise panic on short contigs. Reports the
+// clamp on stderr so the user knows why a contig's plot looks sparse.
+fn clamp_window(seq_len: usize, size: usize, step: usize, record_id: &str) -> (usize, usize) {
+    if seq_len == 0 {
+        eprintln!(
+            "Warning: sequence '{}' is empty; skipping window analysis",
+            record_id
+        );
+        return (0, 0);
+    }
+
+    if size < seq_len {
+        return (size, step);
+    }
+
+    let clamped_size = seq_len - 1;
+
+    if clamped_size == 0 {
+        eprintln!(
+            "Warning: sequence '{}' ({} bp) is too short for window size {}; skipping window analysis",
+            record_id, seq_len, size
+        );
+        return (0, 0);
+    }
+
+    eprintln!(
+        "Warning: window size {} is larger than sequence '{}' ({} bp); clamping window to {}",
+        size, record_id, seq_len, clamped_size
+    );
+
+    (clamped_size, step.min(clamped_size))
+}
+
+// The per-record analysis results `plot` needs to render a chart: the
+// windowed GC averages and their confidence band, plus the cumulative GC
+// skew track and its detected origin/terminus.
+struct RecordAnalysis<'a> {
+    seq_len: usize,
+    window_averages: &'a [f32],
+    step: usize,
+    ci: Option<stats::ConfidenceInterval>,
+    cumulative_skew: &'a [f32],
+    origin_terminus: Option<(usize, usize)>,
+}
+
+fn plot(filename: &str, title: &str, analysis: &RecordAnalysis, out_dir: &str) {
+    let seq_len = analysis.seq_len;
+    let step = analysis.step;
 
-    let mut filename = filename.to_string();
-    filename.push_str(".svg");
+    std::fs::create_dir_all(out_dir).unwrap();
+    let path = std::path::Path::new(out_dir).join(format!("{}.svg", filename));
 
     let root_area = SVGBackend::new(
-        &filename, (2000, 500)
+        &path, (2000, 500)
         ).into_drawing_area();
-    
+
     root_area.fill(&WHITE).unwrap();
 
     let mut context = ChartBuilder::on(&root_area)
@@ -120,9 +307,9 @@ fn plot(filename: &str, title: &str, record: &fasta::Record) {
         .set_label_area_size(LabelAreaPosition::Left, 40)
         .set_label_area_size(LabelAreaPosition::Bottom, 40)
         .caption(title, ("sans-serif", 20))
-        .build_cartesian_2d(0..(record.seq().len()), 0f32..1f32)
+        .build_cartesian_2d(0..seq_len, 0f32..1f32)
         .unwrap();
-    
+
     context
         .configure_mesh()
         .disable_mesh()
@@ -131,29 +318,157 @@ fn plot(filename: &str, title: &str, record: &fasta::Record) {
         .draw()
         .unwrap();
 
-    let window_avg_iter = SlidingWindowAverage::new(record.seq(), SIZE, STEP);
+    if let Some(ci) = &analysis.ci {
+        context.draw_series(std::iter::once(
+            Rectangle::new([(0, ci.lower), (seq_len, ci.upper)], BLUE.mix(0.15).filled())
+        )).unwrap();
+    }
+
     context.draw_series(
         AreaSeries::new(
-            (0..).step_by(STEP).zip(window_avg_iter),
+            (0..).step_by(step).zip(analysis.window_averages.iter().copied()),
             0.0,
             &BLACK.mix(0.1)
         ).border_style(&BLACK)
     ).unwrap();
+
+    let cumulative_skew = analysis.cumulative_skew;
+
+    if !cumulative_skew.is_empty() {
+        let min_skew = cumulative_skew.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max_skew = cumulative_skew.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        let mut secondary = context.set_secondary_coord(0..seq_len, min_skew..max_skew);
+
+        secondary
+            .configure_secondary_axes()
+            .y_desc("Cumulative GC skew")
+            .draw()
+            .unwrap();
+
+        secondary.draw_secondary_series(
+            AreaSeries::new(
+                (0..).step_by(step).zip(cumulative_skew.iter().copied()),
+                0.0,
+                &RED.mix(0.1)
+            ).border_style(&RED)
+        ).unwrap();
+
+        if let Some((origin_idx, terminus_idx)) = analysis.origin_terminus {
+            let origin_pos = origin_idx * step;
+            let terminus_pos = terminus_idx * step;
+
+            secondary.draw_secondary_series(std::iter::once(
+                PathElement::new(vec![(origin_pos, min_skew), (origin_pos, max_skew)], &BLUE)
+            )).unwrap();
+            secondary.draw_secondary_series(std::iter::once(
+                Text::new("origin", (origin_pos, max_skew), ("sans-serif", 15).into_font().color(&BLUE))
+            )).unwrap();
+
+            secondary.draw_secondary_series(std::iter::once(
+                PathElement::new(vec![(terminus_pos, min_skew), (terminus_pos, max_skew)], &GREEN)
+            )).unwrap();
+            secondary.draw_secondary_series(std::iter::once(
+                Text::new("terminus", (terminus_pos, max_skew), ("sans-serif", 15).into_font().color(&GREEN))
+            )).unwrap();
+        }
+    }
+}
+
+// Writes one row per sliding window to `writer` in the shape
+// `record_id\twindow_start\twindow_end\tgc_fraction`. Window bounds are
+// derived from the same size/step offsets used to draw the plot.
+fn write_tsv_rows<W: std::io::Write>(writer: &mut W, record_id: &str, window_averages: &[f32], size: usize, step: usize) -> std::io::Result<()> {
+    for (i, gc_fraction) in window_averages.iter().enumerate() {
+        let window_start = i * step;
+        let window_end = window_start + size;
+        writeln!(writer, "{}\t{}\t{}\t{:.6}", record_id, window_start, window_end, gc_fraction)?;
+    }
+
+    Ok(())
 }
 
+// A record's identity (id + description) together with the sequence it
+// should be analyzed over, once FASTA/FASTQ-specific details (quality
+// masking, owned vs. borrowed data) have been resolved. This is the
+// common shape both input formats are funneled into before plotting.
+struct SeqRecord {
+    id: String,
+    desc: String,
+    seq: Vec<u8>,
+}
+
+fn read_fasta_records<R: std::io::Read>(reader: R) -> Result<Vec<SeqRecord>, std::io::Error> {
+    fasta::Reader::new(reader).records()
+        .map(|result| result.map(|record| SeqRecord {
+            id: record.id().to_string(),
+            desc: record.desc().unwrap_or_default().to_string(),
+            seq: record.seq().to_vec(),
+        }))
+        .collect()
+}
+
+fn read_fastq_records<R: std::io::Read>(reader: R, min_qual: Option<u8>) -> Result<Vec<SeqRecord>, std::io::Error> {
+    fastq::Reader::new(reader).records()
+        .map(|result| result.map_err(std::io::Error::other).map(|record| {
+            let seq = match min_qual {
+                Some(min_qual) => mask_low_quality(record.seq(), record.qual(), min_qual),
+                None => record.seq().to_vec(),
+            };
+            SeqRecord {
+                id: record.id().to_string(),
+                desc: record.desc().unwrap_or_default().to_string(),
+                seq,
+            }
+        }))
+        .collect()
+}
 
 fn main() {
+    const BOOTSTRAP_RESAMPLES: usize = 1000;
+
     let matches = clap::App::new("gc-content")
         .version("0.1")
         .about("Analyzes the GC-Content of a genome")
         .arg(clap::Arg::with_name("FILE")
-            .help("Input file in FASTA format")
+            .help("Input file in FASTA or FASTQ format")
             .required(true)
             .index(1))
+        .arg(clap::Arg::with_name("format")
+            .long("format")
+            .help("Input format (fasta or fastq); auto-detected from the first byte when omitted")
+            .takes_value(true)
+            .possible_values(&["fasta", "fastq"]))
+        .arg(clap::Arg::with_name("min-qual")
+            .long("min-qual")
+            .help("Minimum Phred quality for a FASTQ base to count towards GC content; lower-quality bases are masked as 'other' (ignored for FASTA input)")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("seed")
+            .long("seed")
+            .help("Seed for the bootstrap resampling RNG, for reproducible confidence intervals")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("window")
+            .long("window")
+            .help("Sliding window size in bp; clamped to one less than the sequence length for short contigs")
+            .takes_value(true)
+            .default_value("100000"))
+        .arg(clap::Arg::with_name("step")
+            .long("step")
+            .help("Sliding window step size in bp")
+            .takes_value(true)
+            .default_value("10000"))
+        .arg(clap::Arg::with_name("out-dir")
+            .long("out-dir")
+            .help("Directory to write output files to (created if missing)")
+            .takes_value(true)
+            .default_value("."))
+        .arg(clap::Arg::with_name("tsv")
+            .long("tsv")
+            .help("Also write each record's windowed GC fraction to <out-dir>/gc_windows.tsv"))
         .get_matches();
-    
+
     let filename = matches.value_of("FILE").unwrap();
-    
+
     let file = match File::open(filename) {
         Ok(file) => file,
         Err(err) => {
@@ -162,21 +477,147 @@ fn main() {
         }
     };
 
-    let fasta_reader = fasta::Reader::new(file);
+    let mut reader = BufReader::new(file);
+
+    let format = match matches.value_of("format") {
+        Some(format) => format.to_string(),
+        None => match reader.fill_buf().ok().and_then(|buf| buf.first().copied()) {
+            Some(b'@') => "fastq".to_string(),
+            _ => "fasta".to_string(),
+        },
+    };
+
+    let min_qual = matches.value_of("min-qual").map(|v| v.parse().expect("--min-qual must be an integer"));
+    let seed = matches.value_of("seed").map(|v| v.parse().expect("--seed must be an integer"));
+    let window: usize = matches.value_of("window").unwrap().parse().expect("--window must be an integer");
+    let step: usize = matches.value_of("step").unwrap().parse().expect("--step must be an integer");
+    let out_dir = matches.value_of("out-dir").unwrap();
 
-    match fasta_reader.records().collect::<Result<Vec<_>, _>>() {
+    let records = match format.as_str() {
+        "fastq" => read_fastq_records(reader, min_qual),
+        _ => read_fasta_records(reader),
+    };
+
+    let mut tsv_file = if matches.is_present("tsv") {
+        std::fs::create_dir_all(out_dir).unwrap();
+        let path = std::path::Path::new(out_dir).join("gc_windows.tsv");
+        let mut file = File::create(&path).expect("failed to create TSV output file");
+        writeln!(file, "record_id\twindow_start\twindow_end\tgc_fraction").unwrap();
+        Some(file)
+    } else {
+        None
+    };
+
+    match records {
         Ok(records) => {
             for record in records {
-                println!("[{}] {}", record.id(), record.desc().unwrap_or_default());
-                // println!("  - GC Content: {:.2}%", get_gc_content(&record) * 100.);
-                plot(record.id(), record.desc().unwrap_or_default(), &record);
+                println!("[{}] {}", record.id, record.desc);
+                println!("  - Total GC content: {:.2}%", get_total_gc_content(&record.seq) * 100.);
+
+                let (size, step) = clamp_window(record.seq.len(), window, step, &record.id);
+                let window_averages: Vec<f32> = if size > 0 {
+                    SlidingWindowAverage::new(&record.seq, size, step).collect()
+                } else {
+                    Vec::new()
+                };
+                let ci = stats::bootstrap_ci(&window_averages, BOOTSTRAP_RESAMPLES, seed);
+
+                if let Some(ci) = &ci {
+                    println!(
+                        "  - GC content: {:.2}% (95% CI [{:.2}%, {:.2}%], std dev {:.2}%)",
+                        ci.mean * 100., ci.lower * 100., ci.upper * 100., ci.std_dev * 100.
+                    );
+                }
+
+                let skew: Vec<f32> = if size > 0 {
+                    SlidingWindowSkew::new(&record.seq, size, step).collect()
+                } else {
+                    Vec::new()
+                };
+                let cumulative_skew = cumulative_skew(&skew);
+                let origin_terminus = find_extrema(&cumulative_skew);
+
+                if let Some((origin_idx, terminus_idx)) = origin_terminus {
+                    println!(
+                        "  - Predicted origin at {} bp, terminus at {} bp (cumulative GC skew)",
+                        origin_idx * step, terminus_idx * step
+                    );
+                }
+
+                let analysis = RecordAnalysis {
+                    seq_len: record.seq.len(),
+                    window_averages: &window_averages,
+                    step,
+                    ci,
+                    cumulative_skew: &cumulative_skew,
+                    origin_terminus,
+                };
+                plot(&record.id, &record.desc, &analysis, out_dir);
+
+                if let Some(file) = tsv_file.as_mut() {
+                    write_tsv_rows(file, &record.id, &window_averages, size, step).unwrap();
+                }
             }
         },
         Err(err) => {
-            eprintln!("Error parsing FASTA file: {}", err);
+            eprintln!("Error parsing {} file: {}", format, err);
             return;
         }
     }
 
-    
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_window_keeps_size_when_it_fits() {
+        assert_eq!(clamp_window(1000, 100, 10, "contig"), (100, 10));
+    }
+
+    #[test]
+    fn clamp_window_shrinks_oversized_window() {
+        assert_eq!(clamp_window(50, 100, 10, "short_contig"), (49, 10));
+    }
+
+    #[test]
+    fn clamp_window_handles_empty_sequence() {
+        assert_eq!(clamp_window(0, 100, 10, "empty_contig"), (0, 0));
+    }
+
+    #[test]
+    fn clamp_window_handles_single_base_sequence() {
+        assert_eq!(clamp_window(1, 100, 10, "single_base"), (0, 0));
+    }
+
+    #[test]
+    fn mask_low_quality_replaces_bases_below_threshold() {
+        // Phred scores 40, 10, 40, 2 (qual byte - 33)
+        let seq = b"ACGT";
+        let qual = [73u8, 43, 73, 35];
+        assert_eq!(mask_low_quality(seq, &qual, 20), b"ANGN");
+    }
+
+    #[test]
+    fn cumulative_skew_is_a_running_sum() {
+        let skew = vec![0.1, -0.2, 0.3];
+        let cumulative = cumulative_skew(&skew);
+        let expected = [0.1, -0.1, 0.2];
+
+        for (actual, expected) in cumulative.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 1e-5, "{} != {}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn find_extrema_locates_min_and_max() {
+        assert_eq!(find_extrema(&[0.0, -1.0, 2.0, 0.5]), Some((1, 2)));
+    }
+
+    #[test]
+    fn find_extrema_empty_returns_none() {
+        assert_eq!(find_extrema(&[]), None);
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,90 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Summary of a bootstrap resample of the mean of a set of values: the
+/// overall mean and standard deviation of the resample means, plus the
+/// 95% confidence interval (2.5th/97.5th percentile of the resample means).
+pub struct ConfidenceInterval {
+    pub mean: f32,
+    pub std_dev: f32,
+    pub lower: f32,
+    pub upper: f32,
+}
+
+fn mean(values: &[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+fn std_deviation(values: &[f32], mean: f32) -> f32 {
+    let variance = values.iter()
+        .map(|v| (v - mean).powi(2))
+        .sum::<f32>() / values.len() as f32;
+
+    variance.sqrt()
+}
+
+/// Estimates the sampling distribution of the mean of `values` by
+/// resampling it `resamples` times with replacement, each resample being
+/// the same size as `values`. Returns `None` for an empty input.
+pub fn bootstrap_ci(values: &[f32], resamples: usize, seed: Option<u64>) -> Option<ConfidenceInterval> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut rng: StdRng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let n = values.len();
+    let mut resample_means: Vec<f32> = (0..resamples)
+        .map(|_| {
+            (0..n).map(|_| values[rng.gen_range(0..n)]).sum::<f32>() / n as f32
+        })
+        .collect();
+
+    resample_means.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let lower_idx = ((0.025 * resamples as f32) as usize).min(resamples - 1);
+    let upper_idx = ((0.975 * resamples as f32) as usize).min(resamples - 1);
+
+    let overall_mean = mean(&resample_means);
+    let overall_std_dev = std_deviation(&resample_means, overall_mean);
+
+    Some(ConfidenceInterval {
+        mean: overall_mean,
+        std_dev: overall_std_dev,
+        lower: resample_means[lower_idx],
+        upper: resample_means[upper_idx],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_returns_none() {
+        assert!(bootstrap_ci(&[], 100, Some(1)).is_none());
+    }
+
+    #[test]
+    fn same_seed_is_reproducible() {
+        let values = vec![0.4, 0.5, 0.6, 0.45, 0.55];
+        let a = bootstrap_ci(&values, 200, Some(42)).unwrap();
+        let b = bootstrap_ci(&values, 200, Some(42)).unwrap();
+
+        assert_eq!(a.mean, b.mean);
+        assert_eq!(a.lower, b.lower);
+        assert_eq!(a.upper, b.upper);
+    }
+
+    #[test]
+    fn ci_bounds_the_mean_for_constant_input() {
+        let values = vec![0.5; 50];
+        let ci = bootstrap_ci(&values, 200, Some(7)).unwrap();
+
+        assert!((ci.mean - 0.5).abs() < 1e-6);
+        assert!(ci.lower <= ci.mean && ci.mean <= ci.upper);
+    }
+}